@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::report::ReportConfig;
+use crate::RetryPolicy;
+
+/// Everything that used to be hardcoded in `main`: the URL set plus the
+/// tuning knobs for concurrency, timeouts, retries/backoff, and the
+/// monitoring cadence. Reloaded from disk at the top of every monitoring
+/// round so operators can retune without restarting.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub urls: Vec<String>,
+    pub num_threads: usize,
+    pub timeout_secs: u64,
+    pub retries: u32,
+    pub backoff_base_ms: u64,
+    pub backoff_max_secs: u64,
+    pub interval_secs: u64,
+    #[serde(default = "default_rate_limit_per_sec")]
+    pub rate_limit_per_sec: f64,
+    #[serde(default = "default_rate_limit_capacity")]
+    pub rate_limit_capacity: f64,
+    #[serde(default)]
+    pub report: ReportConfig,
+}
+
+fn default_rate_limit_per_sec() -> f64 {
+    2.0
+}
+
+fn default_rate_limit_capacity() -> f64 {
+    4.0
+}
+
+impl Config {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.retries,
+            base: Duration::from_millis(self.backoff_base_ms),
+            max_backoff: Duration::from_secs(self.backoff_max_secs),
+        }
+    }
+}
+
+/// Loads a `Config` from `path`, parsing as JSON when the extension is
+/// `.json` and TOML otherwise.
+pub fn load(path: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path)?;
+    let config = if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+        serde_json::from_str(&contents)?
+    } else {
+        toml::from_str(&contents)?
+    };
+    Ok(config)
+}
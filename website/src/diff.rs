@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use diffy::create_patch;
+
+use crate::WebsiteStatus;
+
+/// Per-URL snapshot of the most recent round, carried across loop
+/// iterations so the next round can be diffed against it.
+pub type Snapshot = BTreeMap<String, WebsiteStatus>;
+
+/// How much the response time must move before it counts as a change worth
+/// reporting, even if the status itself didn't flip.
+pub struct ChangeThreshold {
+    pub response_time_ms: u128,
+}
+
+impl Default for ChangeThreshold {
+    fn default() -> Self {
+        ChangeThreshold {
+            response_time_ms: 500,
+        }
+    }
+}
+
+pub fn snapshot(results: &[WebsiteStatus]) -> Snapshot {
+    results
+        .iter()
+        .cloned()
+        .map(|status| (status.url.clone(), status))
+        .collect()
+}
+
+/// Compares two rounds' snapshots and prints a unified diff for every URL
+/// whose status variant/code changed, or whose response time moved past
+/// `threshold`. URLs missing from `previous` (new this round) are skipped,
+/// since there is nothing to diff against yet.
+pub fn report_changes(previous: &Snapshot, current: &Snapshot, threshold: &ChangeThreshold) {
+    for (url, current_status) in current {
+        let Some(previous_status) = previous.get(url) else {
+            continue;
+        };
+
+        if !is_change(previous_status, current_status, threshold) {
+            continue;
+        }
+
+        let before = summarize(previous_status);
+        let after = summarize(current_status);
+        let patch = create_patch(&before, &after);
+        println!("--- change detected for {url} ---\n{patch}");
+    }
+}
+
+fn is_change(previous: &WebsiteStatus, current: &WebsiteStatus, threshold: &ChangeThreshold) -> bool {
+    let status_changed = match (&previous.status, &current.status) {
+        (Ok(a), Ok(b)) => a != b,
+        (Err(a), Err(b)) => a != b,
+        _ => true,
+    };
+
+    let before_ms = previous.response_time.as_millis();
+    let after_ms = current.response_time.as_millis();
+    let latency_changed = before_ms.abs_diff(after_ms) >= threshold.response_time_ms;
+
+    status_changed || latency_changed
+}
+
+fn summarize(status: &WebsiteStatus) -> String {
+    format!(
+        "url: {}\nstatus: {:?}\nresponse_time_ms: {}\ntimestamp: {}\nattempts: {}\n",
+        status.url,
+        status.status,
+        status.response_time.as_millis(),
+        status.timestamp.to_rfc3339(),
+        status.attempts,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CheckerError;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn status(result: Result<u16, CheckerError>, response_time_ms: u64) -> WebsiteStatus {
+        WebsiteStatus {
+            url: "https://example.com".to_string(),
+            status: result,
+            response_time: Duration::from_millis(response_time_ms),
+            timestamp: Utc::now(),
+            attempts: 1,
+        }
+    }
+
+    fn threshold() -> ChangeThreshold {
+        ChangeThreshold {
+            response_time_ms: 500,
+        }
+    }
+
+    #[test]
+    fn same_status_code_and_latency_is_not_a_change() {
+        let previous = status(Ok(200), 100);
+        let current = status(Ok(200), 150);
+        assert!(!is_change(&previous, &current, &threshold()));
+    }
+
+    #[test]
+    fn different_status_code_is_a_change() {
+        let previous = status(Ok(200), 100);
+        let current = status(Ok(500), 100);
+        assert!(is_change(&previous, &current, &threshold()));
+    }
+
+    #[test]
+    fn same_error_variant_with_different_value_is_a_change() {
+        // Two `HttpError`s with different status codes share a discriminant
+        // but are not the same failure, so this only passes with a by-value
+        // comparison, not `mem::discriminant`.
+        let previous = status(
+            Err(CheckerError::HttpError {
+                status: 500,
+                location: None,
+            }),
+            100,
+        );
+        let current = status(
+            Err(CheckerError::HttpError {
+                status: 503,
+                location: None,
+            }),
+            100,
+        );
+        assert!(is_change(&previous, &current, &threshold()));
+    }
+
+    #[test]
+    fn ok_to_err_is_a_change() {
+        let previous = status(Ok(200), 100);
+        let current = status(
+            Err(CheckerError::TransportError {
+                error: "connection reset".to_string(),
+            }),
+            100,
+        );
+        assert!(is_change(&previous, &current, &threshold()));
+    }
+
+    #[test]
+    fn latency_move_past_threshold_is_a_change_even_with_same_status() {
+        let previous = status(Ok(200), 100);
+        let current = status(Ok(200), 700);
+        assert!(is_change(&previous, &current, &threshold()));
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// The distinct ways a website check can fail, so callers can react to a
+/// redirect differently than a timeout or a dropped connection instead of
+/// matching on a flattened string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CheckerError {
+    /// The server answered with a non-2xx status. `location` carries the
+    /// `Location` header when present, so redirect chains stay visible.
+    HttpError {
+        status: u16,
+        location: Option<String>,
+    },
+    /// The request did not complete before the configured timeout elapsed.
+    Timeout,
+    /// A lower-level failure: DNS, connection refused, TLS, etc.
+    TransportError { error: String },
+}
+
+impl std::fmt::Display for CheckerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckerError::HttpError { status, location } => match location {
+                Some(location) => write!(f, "HTTP {status} (location: {location})"),
+                None => write!(f, "HTTP {status}"),
+            },
+            CheckerError::Timeout => write!(f, "request timed out"),
+            CheckerError::TransportError { error } => write!(f, "transport error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for CheckerError {}
+
+/// Classifies a `ureq` failure into a `CheckerError`, pulling the `Location`
+/// header off the response when the server returned one.
+pub fn classify(err: ureq::Error) -> CheckerError {
+    match err {
+        ureq::Error::Status(status, response) => {
+            let location = response.header("Location").map(str::to_string);
+            CheckerError::HttpError { status, location }
+        }
+        ureq::Error::Transport(transport) => {
+            // ureq has no `ErrorKind::Timeout`; a timed-out request surfaces
+            // as an `io::Error` of kind `TimedOut` wrapped inside the
+            // transport error, so that's what we have to inspect instead.
+            let is_timeout = std::error::Error::source(&transport)
+                .and_then(|source| source.downcast_ref::<std::io::Error>())
+                .map(|io_err| io_err.kind() == std::io::ErrorKind::TimedOut)
+                .unwrap_or(false);
+
+            if is_timeout {
+                CheckerError::Timeout
+            } else {
+                CheckerError::TransportError {
+                    error: transport.to_string(),
+                }
+            }
+        }
+    }
+}
@@ -1,164 +1,258 @@
-use std::sync::{mpsc::channel, Arc};
+mod config;
+mod diff;
+mod error;
+mod ratelimit;
+mod report;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
+use arc_swap::ArcSwap;
 use serde::Serialize;
-use ureq;
 use chrono::{Utc, DateTime};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use error::CheckerError;
+use ratelimit::RateLimiter;
+
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct WebsiteStatus {
+    pub(crate) url: String,
+    pub(crate) status: Result<u16, CheckerError>,
+    pub(crate) response_time: Duration,
+    pub(crate) timestamp: DateTime<Utc>,
+    /// How many requests this check took, including the one that finally
+    /// succeeded (or the last one that failed). `1` means it succeeded on
+    /// the first try with no retries needed.
+    pub(crate) attempts: u32,
+}
+
+/// Backoff config for retries: after attempt `n` fails, sleep
+/// `base * 2^n` (capped at `max_backoff`) plus jitter in `[0, base)`, so
+/// retries against an overloaded or rate-limited server back off instead of
+/// immediately re-firing and retries across threads don't stay synchronized.
+#[derive(Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    pub(crate) retries: u32,
+    pub(crate) base: Duration,
+    pub(crate) max_backoff: Duration,
+}
 
-#[derive(Serialize, Debug)]
-struct WebsiteStatus {
-    url: String,
-    status: Result<u16, String>,
-    response_time: Duration,
-    timestamp: DateTime<Utc>,
+fn backoff_delay(attempt: u32, policy: &RetryPolicy) -> Duration {
+    let exponential = policy
+        .base
+        .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+        .unwrap_or(policy.max_backoff)
+        .min(policy.max_backoff);
+    let jitter = Duration::from_secs_f64(rand::random::<f64>() * policy.base.as_secs_f64());
+    exponential + jitter
 }
 
-fn check_website(url: &str, timeout: Duration, retries: u32) -> WebsiteStatus {
-    let mut attempts = 0;
+/// Blocking check for a single URL. `ureq` has no async client, so this runs
+/// on a blocking-pool thread via `spawn_blocking` and the result is awaited
+/// from async callers.
+fn check_website_blocking(url: &str, timeout: Duration, policy: &RetryPolicy) -> WebsiteStatus {
     let start_time = Instant::now();
     let timestamp = Utc::now();
+    let mut attempt = 0;
+
+    // Redirect following is an `Agent`-level setting, not a per-request one,
+    // so build an agent with it disabled instead of calling `.redirects()`
+    // on the request itself.
+    let agent = ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .redirects(0)
+        .build();
 
-    while attempts <= retries {
-        let response = ureq::get(url).timeout(timeout).call();
+    loop {
+        attempt += 1;
+        // With auto-follow disabled ureq hands a 3xx back as an `Ok`
+        // response instead of chasing it; it is recorded below as an
+        // `HttpError` with its own `Location` header.
+        let response = agent.get(url).call();
 
         match response {
             Ok(res) => {
+                let code = res.status();
+                let status = if (300..400).contains(&code) {
+                    Err(CheckerError::HttpError {
+                        status: code,
+                        location: res.header("Location").map(str::to_string),
+                    })
+                } else {
+                    Ok(code)
+                };
                 return WebsiteStatus {
                     url: url.to_string(),
-                    status: Ok(res.status()),
+                    status,
                     response_time: start_time.elapsed(),
                     timestamp,
+                    attempts: attempt,
                 };
             }
-            Err(_) if attempts < retries => attempts += 1,
+            Err(_) if attempt <= policy.retries => {
+                thread::sleep(backoff_delay(attempt, policy));
+            }
             Err(err) => {
                 return WebsiteStatus {
                     url: url.to_string(),
-                    status: Err(err.to_string()),
+                    status: Err(error::classify(err)),
                     response_time: start_time.elapsed(),
                     timestamp,
+                    attempts: attempt,
                 };
             }
         }
     }
+}
 
-    WebsiteStatus {
-        url: url.to_string(),
-        status: Err("Max retries reached".to_string()),
-        response_time: start_time.elapsed(),
-        timestamp,
-    }
+async fn check_website(url: String, timeout: Duration, policy: RetryPolicy) -> WebsiteStatus {
+    let for_panic = url.clone();
+    tokio::task::spawn_blocking(move || check_website_blocking(&url, timeout, &policy))
+        .await
+        .unwrap_or_else(|_| WebsiteStatus {
+            url: for_panic,
+            status: Err(CheckerError::TransportError {
+                error: "check task panicked".to_string(),
+            }),
+            response_time: Duration::default(),
+            timestamp: Utc::now(),
+            attempts: 0,
+        })
 }
 
-fn monitor_websites(
+/// Checks every URL with at most `num_threads` requests in flight at once.
+/// Each URL gets its own task; a semaphore of size `num_threads` gates how
+/// many are allowed to be mid-request simultaneously, so a slow URL no
+/// longer stalls a whole static slice of the list. Results are collected via
+/// `JoinSet` in completion order and returned once every task has finished.
+async fn monitor_websites(
     urls: Arc<Vec<String>>,
     num_threads: usize,
     timeout: Duration,
-    retries: u32,
-    tx: std::sync::mpsc::Sender<WebsiteStatus>,
-) {
-    let mut handles = Vec::new();
-
-    for i in 0..num_threads {
-        let urls = Arc::clone(&urls);
-        let tx = tx.clone();
-
-        let handle = thread::spawn(move || {
-            for (index, url) in urls.iter().enumerate() {
-                if index % num_threads == i {
-                    let status = check_website(url, timeout, retries);
-                    tx.send(status).unwrap();
-                }
-            }
-        });
+    policy: RetryPolicy,
+    limiter: Arc<RateLimiter>,
+) -> Vec<WebsiteStatus> {
+    let semaphore = Arc::new(Semaphore::new(num_threads));
+    let mut tasks = JoinSet::new();
 
-        handles.push(handle);
+    for url in urls.iter().cloned() {
+        let semaphore = Arc::clone(&semaphore);
+        let limiter = Arc::clone(&limiter);
+        tasks.spawn(async move {
+            // Wait for a per-host token before taking a global concurrency
+            // slot, so a throttled host can't tie up a semaphore permit
+            // (and starve unrelated hosts) while it waits for its bucket.
+            limiter.acquire(&ratelimit::host_key(&url)).await;
+            let _permit = semaphore.acquire_owned().await.expect("semaphore closed");
+            check_website(url, timeout, policy).await
+        });
     }
 
-    for handle in handles {
-        handle.join().unwrap();
+    let mut results = Vec::with_capacity(urls.len());
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(status) => results.push(status),
+            Err(err) => eprintln!("website check task panicked: {err}"),
+        }
     }
+    results
 }
 
-fn periodic_monitoring(
-    urls: Vec<String>,
-    num_threads: usize,
-    timeout: Duration,
-    retries: u32,
-    interval: Duration,
-) {
-    let urls = Arc::new(urls);
+/// Re-reads `config_path` at the top of every round, so operators can
+/// add/remove URLs or retune timeouts without restarting. The freshly
+/// loaded config is swapped into `current` and then read once per round
+/// via `load_full`, giving every task spawned in that round a consistent
+/// snapshot even if the file changes again mid-round.
+async fn periodic_monitoring(config_path: PathBuf) {
+    let initial = config::load(&config_path)
+        .unwrap_or_else(|err| panic!("failed to load config {}: {err}", config_path.display()));
+    let current = ArcSwap::new(Arc::new(initial));
+
+    let mut previous_snapshot: diff::Snapshot = BTreeMap::new();
+    let change_threshold = diff::ChangeThreshold::default();
 
     loop {
-        let (tx, rx) = channel();
+        match config::load(&config_path) {
+            Ok(fresh) => current.store(Arc::new(fresh)),
+            Err(err) => eprintln!(
+                "failed to reload config {}, keeping previous: {err}",
+                config_path.display()
+            ),
+        }
+        let config = current.load_full();
 
-        thread::spawn({
-            let urls = Arc::clone(&urls);
-            move || {
-                monitor_websites(urls, num_threads, timeout, retries, tx);
-            }
-        });
+        let urls = Arc::new(config.urls.clone());
+        let limiter = Arc::new(RateLimiter::new(
+            config.rate_limit_per_sec,
+            config.rate_limit_capacity,
+        ));
+
+        let results = monitor_websites(
+            urls,
+            config.num_threads,
+            config.timeout(),
+            config.retry_policy(),
+            limiter,
+        )
+        .await;
 
-        for status in rx {
+        for status in &results {
             println!("{:?}", status);
         }
 
-        thread::sleep(interval);
+        if let Err(err) = report::record_round(&config.report, &results) {
+            eprintln!("failed to persist monitoring round: {err}");
+        }
+
+        let current_snapshot = diff::snapshot(&results);
+        diff::report_changes(&previous_snapshot, &current_snapshot, &change_threshold);
+        previous_snapshot = current_snapshot;
+
+        tokio::time::sleep(config.interval()).await;
     }
 }
 
-fn main() {
-    let urls = vec![
-        "https://www.google.com".to_string(),
-        "https://www.youtube.com".to_string(),
-        "https://www.facebook.com".to_string(),
-        "https://www.twitter.com".to_string(),
-        "https://www.instagram.com".to_string(),
-        "https://www.linkedin.com".to_string(),
-        "https://www.reddit.com".to_string(),
-        "https://www.tiktok.com".to_string(),
-        "https://www.snapchat.com".to_string(),
-        "https://www.whatsapp.com".to_string(),
-        "https://www.pinterest.com".to_string(),
-        "https://www.tumblr.com".to_string(),
-        "https://www.twitch.tv".to_string(),
-        "https://www.medium.com".to_string(),
-        "https://www.disney.com".to_string(),
-        "https://www.coca-cola.com".to_string(),
-        "https://www.pepsi.com".to_string(),
-        "https://www.sprite.com".to_string(),
-        "https://www.drpepper.com".to_string(),
-        "https://www.fanta.com".to_string(),
-        "https://www.microsoft.com".to_string(),
-        "https://www.apple.com".to_string(),
-        "https://www.netflix.com".to_string(),
-        "https://www.spotify.com".to_string(),
-        "https://www.amazon.com".to_string(),
-        "https://www.ebay.com".to_string(),
-        "https://www.walmart.com".to_string(),
-        "https://www.target.com".to_string(),
-        "https://www.adobe.com".to_string(),
-        "https://www.nasa.gov".to_string(),
-        "https://www.tesla.com".to_string(),
-        "https://www.weather.com".to_string(),
-        "https://www.tripadvisor.com".to_string(),
-        "https://www.airbnb.com".to_string(),
-        "https://www.booking.com".to_string(),
-        "https://www.wikipedia.org".to_string(),
-        "https://themousepadcompany.com".to_string(),
-        "https://www.cnn.com".to_string(),
-        "https://www.crazygames.com".to_string(),
-        "https://www.nytimes.com".to_string(),
-        "https://www.roblox.com".to_string(),
-        "https://www.riotgames.com".to_string(),
-        "https://www.forever21.com".to_string(),
-        "https://www.blizzard.com".to_string(),
-    ];
-
-    let num_threads = 8;
-    let timeout = Duration::from_secs(5);
-    let retries = 3;
-    let interval = Duration::from_secs(60);
-
-    periodic_monitoring(urls, num_threads, timeout, retries, interval);
+#[tokio::main]
+async fn main() {
+    let config_path = PathBuf::from("monitor_config.toml");
+
+    periodic_monitoring(config_path).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            retries: 5,
+            base: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn backoff_delay_grows_exponentially_with_jitter() {
+        let policy = policy();
+
+        for attempt in 0..4 {
+            let delay = backoff_delay(attempt, &policy);
+            let exponential = policy.base * (1u32 << attempt);
+            assert!(delay >= exponential);
+            assert!(delay < exponential + policy.base);
+        }
+    }
+
+    #[test]
+    fn backoff_delay_is_capped_at_max_backoff() {
+        let policy = policy();
+
+        let delay = backoff_delay(32, &policy);
+        assert!(delay >= policy.max_backoff);
+        assert!(delay < policy.max_backoff + policy.base);
+    }
 }
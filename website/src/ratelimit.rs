@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+/// A token bucket for a single host: refills `rate` tokens/sec up to
+/// `capacity`, draining one token per request.
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    rate: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(capacity: f64, rate: f64) -> Self {
+        Bucket {
+            tokens: capacity,
+            capacity,
+            rate,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = Instant::now();
+    }
+
+    /// Takes a token if one is available; otherwise returns how long to
+    /// wait before a token will be.
+    fn try_take(&mut self) -> Result<(), Duration> {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            let deficit = 1.0 - self.tokens;
+            Err(Duration::from_secs_f64(deficit / self.rate))
+        }
+    }
+}
+
+/// Bounds requests-per-second to each host independently, keyed by host, so
+/// a URL list that shares a domain can't trip that domain's own rate limits
+/// even though the global concurrency cap would otherwise allow it.
+pub struct RateLimiter {
+    rate: f64,
+    capacity: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+/// Floor for `RateLimiter::rate`. `Bucket::try_take` divides by the rate to
+/// compute how long to wait for the next token, so a `rate` of `0.0` (or
+/// negative, from a misconfigured file) would divide by zero and panic in
+/// `Duration::from_secs_f64`.
+const MIN_RATE_PER_SEC: f64 = 1e-3;
+
+impl RateLimiter {
+    pub fn new(rate: f64, capacity: f64) -> Self {
+        RateLimiter {
+            rate: rate.max(MIN_RATE_PER_SEC),
+            capacity,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Blocks the calling task until a token is available for `host`,
+    /// creating that host's bucket (full) on first use.
+    pub async fn acquire(&self, host: &str) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().await;
+                let bucket = buckets
+                    .entry(host.to_string())
+                    .or_insert_with(|| Bucket::new(self.capacity, self.rate));
+                bucket.try_take()
+            };
+
+            match wait {
+                Ok(()) => return,
+                Err(delay) => tokio::time::sleep(delay).await,
+            }
+        }
+    }
+}
+
+/// Extracts the host portion of a URL for bucket keying, falling back to the
+/// full URL when it can't be parsed so an unparsable URL still gets its own
+/// (private) bucket instead of panicking.
+pub fn host_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_take_drains_then_refuses_until_refilled() {
+        let mut bucket = Bucket::new(1.0, 1.0);
+        assert!(bucket.try_take().is_ok());
+        assert!(bucket.try_take().is_err());
+    }
+
+    #[test]
+    fn try_take_allows_bursts_up_to_capacity() {
+        let mut bucket = Bucket::new(3.0, 1.0);
+        assert!(bucket.try_take().is_ok());
+        assert!(bucket.try_take().is_ok());
+        assert!(bucket.try_take().is_ok());
+        assert!(bucket.try_take().is_err());
+    }
+
+    #[test]
+    fn try_take_does_not_panic_with_rate_limiter_clamped_rate() {
+        // A `rate` of `0.0` would make `try_take` divide by zero when
+        // computing the wait for the next token. `RateLimiter::new` clamps
+        // it before it ever reaches a `Bucket`, so feed that clamped value
+        // through here to pin down the panic this guards against.
+        let limiter = RateLimiter::new(0.0, 1.0);
+        let mut bucket = Bucket::new(1.0, limiter.rate);
+        assert!(bucket.try_take().is_ok());
+        let wait = bucket.try_take().expect_err("bucket should be empty");
+        assert!(wait.as_secs_f64().is_finite());
+    }
+
+    #[test]
+    fn rate_limiter_new_clamps_nonpositive_rate() {
+        let limiter = RateLimiter::new(0.0, 1.0);
+        assert!(limiter.rate > 0.0);
+
+        let limiter = RateLimiter::new(-5.0, 1.0);
+        assert!(limiter.rate > 0.0);
+    }
+}
@@ -0,0 +1,92 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::WebsiteStatus;
+
+/// Where (and in what formats) each monitoring round gets persisted. Both
+/// paths are optional: a path of `None` disables that sink. Lives inside
+/// `Config` so operators can repoint a sink from the config file like every
+/// other tuning knob, instead of recompiling. An omitted path falls back to
+/// its default, so a sink is only disabled by an explicit `null`, which a
+/// JSON config can express and a TOML config cannot.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReportConfig {
+    #[serde(default = "default_json_path")]
+    pub json_path: Option<String>,
+    #[serde(default = "default_csv_path")]
+    pub csv_path: Option<String>,
+}
+
+fn default_json_path() -> Option<String> {
+    Some("status_log.ndjson".to_string())
+}
+
+fn default_csv_path() -> Option<String> {
+    Some("status_log.csv".to_string())
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        ReportConfig {
+            json_path: default_json_path(),
+            csv_path: default_csv_path(),
+        }
+    }
+}
+
+/// Appends one round of results to the configured JSON/CSV files. Files are
+/// opened in append mode (and a CSV header written once, on creation) so
+/// successive rounds build a time series instead of overwriting history.
+pub fn record_round(config: &ReportConfig, results: &[WebsiteStatus]) -> io::Result<()> {
+    if let Some(path) = &config.json_path {
+        append_json(path, results)?;
+    }
+    if let Some(path) = &config.csv_path {
+        append_csv(path, results)?;
+    }
+    Ok(())
+}
+
+fn append_json(path: &str, results: &[WebsiteStatus]) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for status in results {
+        let line = serde_json::to_string(status).map_err(io::Error::other)?;
+        writeln!(file, "{line}")?;
+    }
+    Ok(())
+}
+
+fn append_csv(path: &str, results: &[WebsiteStatus]) -> io::Result<()> {
+    let is_new = !Path::new(path).exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "url,status,response_time_ms,timestamp,attempts")?;
+    }
+    for status in results {
+        let status_field = match &status.status {
+            Ok(code) => code.to_string(),
+            Err(err) => err.to_string(),
+        };
+        writeln!(
+            file,
+            "{},{},{},{},{}",
+            csv_escape(&status.url),
+            csv_escape(&status_field),
+            status.response_time.as_millis(),
+            status.timestamp.to_rfc3339(),
+            status.attempts,
+        )?;
+    }
+    Ok(())
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}